@@ -0,0 +1,241 @@
+//! Causal-context tokens for concurrent-write reconciliation.
+//!
+//! Follows Garage's K2V causal-context model: each stored value carries a
+//! version vector (a map from writer id to a monotonically increasing
+//! counter). A `get` returns an opaque [`CausalContext`] token encoding the
+//! version vector of every value currently present; a `put` passes that
+//! token back, the store increments its own writer's entry, and any
+//! existing value whose vector is dominated by the incoming one is
+//! discarded while concurrent values are kept side by side (or reconciled
+//! down to the most recent write, depending on the configured
+//! [`ConflictResolution`]).
+
+use std::collections::BTreeMap;
+
+/// Identifies a writer contributing counters to a [`VersionVector`].
+pub type WriterId = String;
+
+/// A map from writer id to its monotonically increasing counter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionVector(BTreeMap<WriterId, u64>);
+
+impl VersionVector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn counter(&self, writer: &str) -> u64 {
+        self.0.get(writer).copied().unwrap_or(0)
+    }
+
+    /// `self` dominates `other` if it is ahead-or-even on every writer and
+    /// strictly ahead on at least one, i.e. `other`'s write causally
+    /// precedes (or is superseded by) `self`'s.
+    pub fn dominates(&self, other: &VersionVector) -> bool {
+        if self == other {
+            return false;
+        }
+        other
+            .0
+            .iter()
+            .all(|(writer, &count)| self.counter(writer) >= count)
+    }
+
+    /// Two vectors are concurrent when neither dominates the other.
+    pub fn is_concurrent_with(&self, other: &VersionVector) -> bool {
+        self != other && !self.dominates(other) && !other.dominates(self)
+    }
+
+    /// Element-wise max across both vectors: a writer that has observed
+    /// every value in a set of concurrent versions has, by definition,
+    /// observed at least as much of each writer's history as any one of
+    /// them.
+    pub fn merge(&self, other: &VersionVector) -> VersionVector {
+        let mut merged = self.0.clone();
+        for (writer, &count) in &other.0 {
+            let entry = merged.entry(writer.clone()).or_insert(0);
+            *entry = (*entry).max(count);
+        }
+        VersionVector(merged)
+    }
+
+    /// Bumps `writer`'s own counter by one, as a writer does to its own
+    /// entry before storing a new value.
+    pub fn incremented(&self, writer: &str) -> VersionVector {
+        let mut bumped = self.0.clone();
+        *bumped.entry(writer.to_string()).or_insert(0) += 1;
+        bumped.into()
+    }
+}
+
+impl From<BTreeMap<WriterId, u64>> for VersionVector {
+    fn from(map: BTreeMap<WriterId, u64>) -> Self {
+        VersionVector(map)
+    }
+}
+
+/// The opaque token returned by a `get` and passed back on the next `put`.
+/// Encodes the version vector of every value seen so a writer can prove
+/// what it has observed without the application needing to understand the
+/// internal representation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CausalContext(pub(crate) VersionVector);
+
+impl CausalContext {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn vector(&self) -> &VersionVector {
+        &self.0
+    }
+}
+
+/// How a store reconciles concurrent writes to the same key on read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Collapse concurrent values down to the one with the highest total
+    /// counter sum (an arbitrary but deterministic tie-break standing in
+    /// for a real last-writer-wins timestamp comparison).
+    LastWriterWins,
+    /// Surface every concurrent value; the application merges them.
+    KeepConcurrent,
+}
+
+/// One key's set of currently-live, pairwise-concurrent versions.
+#[derive(Debug, Clone, Default)]
+pub struct VersionedValues<V> {
+    entries: Vec<(VersionVector, V)>,
+}
+
+impl<V: Clone> VersionedValues<V> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// The causal context covering every value currently stored here.
+    pub fn context(&self) -> CausalContext {
+        let merged = self
+            .entries
+            .iter()
+            .fold(VersionVector::new(), |acc, (v, _)| acc.merge(v));
+        CausalContext(merged)
+    }
+
+    /// Inserts `value` under `writer`'s next counter relative to `seen`,
+    /// dropping any existing value the new write causally supersedes and
+    /// keeping values that are concurrent with it.
+    pub fn put(&mut self, writer: &str, seen: &CausalContext, value: V) -> CausalContext {
+        let new_vector = seen.vector().incremented(writer);
+        self.entries
+            .retain(|(existing, _)| !new_vector.dominates(existing));
+        self.entries.push((new_vector.clone(), value));
+        CausalContext(new_vector)
+    }
+
+    /// Returns the live values per `resolution`.
+    pub fn get(&self, resolution: ConflictResolution) -> Vec<V> {
+        match resolution {
+            ConflictResolution::KeepConcurrent => {
+                self.entries.iter().map(|(_, v)| v.clone()).collect()
+            }
+            ConflictResolution::LastWriterWins => self
+                .entries
+                .iter()
+                .max_by_key(|(vector, _)| vector.0.values().sum::<u64>())
+                .map(|(_, v)| vec![v.clone()])
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// The shared, storage-side keyspace that one or more writers reconcile
+/// concurrent writes against. A [`crate::session::Session`] owns a private
+/// one by default, or several sessions can be pointed at the same
+/// `Arc<CausalStorage>` to model racing writers hitting one multi-master
+/// storage/queryable.
+#[derive(Debug, Default)]
+pub struct CausalStorage {
+    keys: std::sync::Mutex<std::collections::HashMap<crate::key_expr::KeyExpr, VersionedValues<Vec<u8>>>>,
+}
+
+impl CausalStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put(
+        &self,
+        writer: &str,
+        key_expr: impl Into<crate::key_expr::KeyExpr>,
+        seen: &CausalContext,
+        payload: Vec<u8>,
+    ) -> CausalContext {
+        self.keys
+            .lock()
+            .unwrap()
+            .entry(key_expr.into())
+            .or_default()
+            .put(writer, seen, payload)
+    }
+
+    pub fn get(
+        &self,
+        key_expr: impl Into<crate::key_expr::KeyExpr>,
+        resolution: ConflictResolution,
+    ) -> (Vec<Vec<u8>>, CausalContext) {
+        let key_expr = key_expr.into();
+        let keys = self.keys.lock().unwrap();
+        let Some(values) = keys.get(&key_expr) else {
+            return (Vec::new(), CausalContext::empty());
+        };
+        (values.get(resolution), values.context())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequential_writes_from_the_same_writer_replace_each_other() {
+        let mut store = VersionedValues::new();
+        let ctx = store.put("writer-a", &CausalContext::empty(), "v1");
+        store.put("writer-a", &ctx, "v2");
+
+        assert_eq!(store.get(ConflictResolution::KeepConcurrent), vec!["v2"]);
+    }
+
+    #[test]
+    fn concurrent_writes_are_both_kept_and_merged_on_resolve() {
+        let mut store = VersionedValues::new();
+        let seen = CausalContext::empty();
+        store.put("writer-a", &seen, "from-a");
+        store.put("writer-b", &seen, "from-b");
+
+        let mut kept = store.get(ConflictResolution::KeepConcurrent);
+        kept.sort();
+        assert_eq!(kept, vec!["from-a", "from-b"]);
+
+        // LastWriterWins picks one deterministic winner instead of two.
+        assert_eq!(store.get(ConflictResolution::LastWriterWins).len(), 1);
+    }
+
+    #[test]
+    fn a_write_seeded_from_the_merged_context_supersedes_both_concurrent_values() {
+        let mut store = VersionedValues::new();
+        let seen = CausalContext::empty();
+        store.put("writer-a", &seen, "from-a");
+        store.put("writer-b", &seen, "from-b");
+
+        let merged_ctx = store.context();
+        store.put("writer-a", &merged_ctx, "resolved");
+
+        assert_eq!(
+            store.get(ConflictResolution::KeepConcurrent),
+            vec!["resolved"]
+        );
+    }
+}
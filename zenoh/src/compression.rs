@@ -0,0 +1,142 @@
+//! Per-publisher payload compression, applied once above the network codec
+//! so payloads are compressed before being handed to the transport and
+//! decompressed transparently before a `Subscriber` callback ever sees
+//! `sample.payload()`.
+//!
+//! Compression is decided by the publisher, not negotiated globally: each
+//! compressed payload is tagged with the codec that produced it so a peer
+//! that doesn't recognise the tag can still route the bytes untouched.
+//! [`crate::session::Session::put_builder`] is the only caller of [`encode`]
+//! in this crate; [`crate::session::Session::get`] is the only caller of
+//! [`decode`] — a subscriber/reader never has to know a payload was
+//! compressed at all.
+
+use std::fmt;
+
+/// Compression codec [`crate::session::Session::put_builder`]'s
+/// [`crate::session::PutBuilder::compression`] can apply to a payload before
+/// it is stored/sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Payload is sent as-is.
+    #[default]
+    None,
+    /// Fast, low-ratio codec (LZ4 block format): cheap enough to apply per-message.
+    Lz4,
+    /// Higher-ratio codec (zstd, default level) for bandwidth-constrained links.
+    Zstd,
+}
+
+impl Compression {
+    /// The single-byte tag carried alongside the payload so an older peer
+    /// that doesn't understand compression can still forward the bytes.
+    const fn tag(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Lz4 => 1,
+            Compression::Zstd => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Lz4),
+            2 => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when a compressed payload's codec tag is missing,
+/// unrecognised, or the body itself doesn't decode under that codec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecompressError {
+    UnknownTag(u8),
+    Corrupt(String),
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecompressError::UnknownTag(tag) => {
+                write!(f, "unrecognised compression codec tag: {tag}")
+            }
+            DecompressError::Corrupt(reason) => write!(f, "corrupt compressed payload: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for DecompressError {}
+
+/// Compresses `payload` with `codec`, returning the wire bytes: a
+/// one-byte codec tag followed by the (possibly compressed) payload.
+pub fn encode(codec: Compression, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 1);
+    out.push(codec.tag());
+    match codec {
+        Compression::None => out.extend_from_slice(payload),
+        Compression::Lz4 => out.extend(lz4_flex::block::compress_prepend_size(payload)),
+        Compression::Zstd => {
+            out.extend(zstd::stream::encode_all(payload, 0).expect("in-memory zstd encode"))
+        }
+    }
+    out
+}
+
+/// Reverses [`encode`], returning the original payload. Unknown tags are
+/// rejected rather than silently routed as raw bytes so the caller can
+/// decide whether to pass the message through unmodified.
+pub fn decode(wire: &[u8]) -> Result<Vec<u8>, DecompressError> {
+    let (&tag, rest) = wire.split_first().ok_or(DecompressError::UnknownTag(0))?;
+    let codec = Compression::from_tag(tag).ok_or(DecompressError::UnknownTag(tag))?;
+    match codec {
+        Compression::None => Ok(rest.to_vec()),
+        Compression::Lz4 => lz4_flex::block::decompress_size_prepended(rest)
+            .map_err(|err| DecompressError::Corrupt(err.to_string())),
+        Compression::Zstd => zstd::stream::decode_all(rest)
+            .map_err(|err| DecompressError::Corrupt(err.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none_is_identity() {
+        let payload = vec![1, 2, 3, 4, 5];
+        let wire = encode(Compression::None, &payload);
+        assert_eq!(decode(&wire).unwrap(), payload);
+    }
+
+    #[test]
+    fn lz4_round_trips_small_and_large_payloads() {
+        for size in [1_024usize, 100_000] {
+            let payload = vec![0u8; size];
+            let wire = encode(Compression::Lz4, &payload);
+            assert!(wire.len() < payload.len(), "uniform payload should shrink");
+            assert_eq!(decode(&wire).unwrap(), payload);
+        }
+    }
+
+    #[test]
+    fn zstd_round_trips_mixed_payload() {
+        let payload: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+        let wire = encode(Compression::Zstd, &payload);
+        assert!(wire.len() < payload.len(), "repeating payload should shrink");
+        assert_eq!(decode(&wire).unwrap(), payload);
+    }
+
+    #[test]
+    fn unknown_tag_is_rejected() {
+        assert_eq!(decode(&[42, 0, 0]), Err(DecompressError::UnknownTag(42)));
+    }
+
+    #[test]
+    fn lz4_rejects_corrupt_body() {
+        let mut wire = encode(Compression::Lz4, b"hello world hello world");
+        wire.truncate(3);
+        assert!(matches!(decode(&wire), Err(DecompressError::Corrupt(_))));
+    }
+}
@@ -0,0 +1,336 @@
+//! Automatic session reconnection with a background connectivity monitor.
+//!
+//! Rather than relying on the next publish/get to lazily notice a dropped
+//! link, a [`Monitor`] periodically probes connectivity and, on failure,
+//! retries with exponential backoff, emitting [`ConnectivityStatus`]
+//! changes an application can subscribe to via [`Connectivity::listen`].
+//!
+//! The monitor probes/reconnects against a [`Transport`] rather than raw
+//! caller-supplied closures, so reconnection is driven by `connect.endpoints`
+//! ([`ConnectConfig`]) the way the request asks, instead of bespoke checks
+//! unrelated to any endpoint. This crate has no real socket layer to dial,
+//! so [`EndpointTransport`] plays that role against an [`EndpointRegistry`]:
+//! an in-process, shared "is this endpoint reachable right now" table,
+//! the same role [`crate::session::Session`]'s in-memory store plays for a
+//! real network key/value store. [`Transport`] stays a trait so a test (or
+//! a future real socket-backed implementation) can supply its own.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// `connect.endpoints`: the ordered list of endpoints [`EndpointTransport`]
+/// probes and fails over across, e.g. `["tcp/10.0.0.1:7447",
+/// "tcp/10.0.0.2:7447"]`.
+#[derive(Debug, Clone)]
+pub struct ConnectConfig {
+    pub endpoints: Vec<String>,
+}
+
+/// A shared, in-process stand-in for real endpoint liveness: there's no
+/// socket layer in this crate to actually dial, so tests (and callers)
+/// flip an endpoint's entry here to simulate it dropping or recovering,
+/// and every [`EndpointTransport`] built against the same registry sees
+/// the same view - the same role `Session`'s shared store plays for
+/// multiple sessions over the same keys.
+#[derive(Clone, Default)]
+pub struct EndpointRegistry(Arc<Mutex<HashMap<String, bool>>>);
+
+impl EndpointRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks `endpoint` reachable or unreachable. Unknown endpoints are
+    /// reachable by default, so a freshly constructed registry behaves
+    /// like a healthy network until told otherwise.
+    pub fn set_reachable(&self, endpoint: &str, reachable: bool) {
+        self.0.lock().unwrap().insert(endpoint.to_string(), reachable);
+    }
+
+    fn is_reachable(&self, endpoint: &str) -> bool {
+        self.0.lock().unwrap().get(endpoint).copied().unwrap_or(true)
+    }
+}
+
+/// What a [`Monitor`] probes and reconnects against.
+pub trait Transport: Send + 'static {
+    /// A cheap, non-blocking liveness check against the transport's
+    /// current endpoint.
+    fn probe(&self) -> bool;
+    /// Attempts to (re)establish a connection, possibly to a different
+    /// configured endpoint than the last one; returns whether it succeeded.
+    fn reconnect(&mut self) -> bool;
+}
+
+/// A [`Transport`] over [`ConnectConfig::endpoints`], backed by an
+/// [`EndpointRegistry`]: probing checks the currently-selected endpoint's
+/// registry entry, and reconnecting fails over to the first reachable
+/// endpoint in the configured order.
+pub struct EndpointTransport {
+    endpoints: Vec<String>,
+    registry: EndpointRegistry,
+    current: usize,
+}
+
+impl EndpointTransport {
+    pub fn new(config: ConnectConfig, registry: EndpointRegistry) -> Self {
+        Self {
+            endpoints: config.endpoints,
+            registry,
+            current: 0,
+        }
+    }
+
+    /// The endpoint this transport currently considers itself connected to.
+    pub fn current_endpoint(&self) -> Option<&str> {
+        self.endpoints.get(self.current).map(String::as_str)
+    }
+}
+
+impl Transport for EndpointTransport {
+    fn probe(&self) -> bool {
+        self.current_endpoint()
+            .is_some_and(|endpoint| self.registry.is_reachable(endpoint))
+    }
+
+    fn reconnect(&mut self) -> bool {
+        for offset in 0..self.endpoints.len() {
+            let candidate = (self.current + offset) % self.endpoints.len();
+            if self.registry.is_reachable(&self.endpoints[candidate]) {
+                self.current = candidate;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// The connectivity state of a monitored link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectivityStatus {
+    /// The last probe succeeded.
+    Connected,
+    /// A probe just failed; a reconnect attempt is about to start.
+    Degraded,
+    /// A reconnect attempt is in flight (possibly backing off between
+    /// retries).
+    Reconnecting,
+}
+
+impl ConnectivityStatus {
+    fn to_tag(self) -> u8 {
+        match self {
+            ConnectivityStatus::Connected => 0,
+            ConnectivityStatus::Degraded => 1,
+            ConnectivityStatus::Reconnecting => 2,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Self {
+        match tag {
+            0 => ConnectivityStatus::Connected,
+            1 => ConnectivityStatus::Degraded,
+            _ => ConnectivityStatus::Reconnecting,
+        }
+    }
+}
+
+/// How long to wait before the `attempt`-th (0-indexed) reconnect retry:
+/// doubles the base interval each attempt, capped at `max_backoff`.
+pub fn backoff_for_attempt(interval: Duration, max_backoff: Duration, attempt: u32) -> Duration {
+    interval
+        .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+        .unwrap_or(max_backoff)
+        .min(max_backoff)
+}
+
+type Listener = Box<dyn Fn(ConnectivityStatus) + Send + 'static>;
+
+struct Shared {
+    status: AtomicU8,
+    listeners: Mutex<Vec<Listener>>,
+}
+
+impl Shared {
+    fn set(&self, status: ConnectivityStatus) {
+        self.status.store(status.to_tag(), Ordering::SeqCst);
+        for listener in self.listeners.lock().unwrap().iter() {
+            listener(status);
+        }
+    }
+}
+
+/// A handle applications use to read or subscribe to connectivity status
+/// changes; returned by [`Monitor::connectivity`] (and, on a full
+/// `Session`, by `session.connectivity()`).
+#[derive(Clone)]
+pub struct Connectivity {
+    shared: Arc<Shared>,
+}
+
+impl Connectivity {
+    pub fn status(&self) -> ConnectivityStatus {
+        ConnectivityStatus::from_tag(self.shared.status.load(Ordering::SeqCst))
+    }
+
+    /// Registers a callback invoked on every status change. There is no
+    /// unregister handle: like the admin-space subscriptions elsewhere in
+    /// this crate, listeners live as long as the [`Monitor`] does.
+    pub fn listen(&self, callback: impl Fn(ConnectivityStatus) + Send + 'static) {
+        self.shared.listeners.lock().unwrap().push(Box::new(callback));
+    }
+}
+
+/// Periodically probes a link and reconnects with exponential backoff on
+/// failure, per `connect.reconnect.{enabled, interval, max_backoff}`.
+pub struct Monitor {
+    shared: Arc<Shared>,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Monitor {
+    /// Spawns the background probe loop against `transport`. `interval` is
+    /// how often to probe while healthy, and reconnect retries back off
+    /// from `interval` up to `max_backoff`.
+    pub fn spawn(interval: Duration, max_backoff: Duration, mut transport: impl Transport) -> Self {
+        let shared = Arc::new(Shared {
+            status: AtomicU8::new(ConnectivityStatus::Connected.to_tag()),
+            listeners: Mutex::new(Vec::new()),
+        });
+        let running = Arc::new(AtomicBool::new(true));
+
+        let loop_shared = shared.clone();
+        let loop_running = running.clone();
+        let handle = std::thread::spawn(move || {
+            while loop_running.load(Ordering::SeqCst) {
+                std::thread::sleep(interval);
+                if !loop_running.load(Ordering::SeqCst) {
+                    break;
+                }
+                if transport.probe() {
+                    continue;
+                }
+                loop_shared.set(ConnectivityStatus::Degraded);
+                let mut attempt = 0;
+                loop {
+                    if !loop_running.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    loop_shared.set(ConnectivityStatus::Reconnecting);
+                    if transport.reconnect() {
+                        loop_shared.set(ConnectivityStatus::Connected);
+                        break;
+                    }
+                    std::thread::sleep(backoff_for_attempt(interval, max_backoff, attempt));
+                    attempt += 1;
+                }
+            }
+        });
+
+        Self {
+            shared,
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn connectivity(&self) -> Connectivity {
+        Connectivity {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let interval = Duration::from_millis(10);
+        let max = Duration::from_millis(100);
+        assert_eq!(backoff_for_attempt(interval, max, 0), Duration::from_millis(10));
+        assert_eq!(backoff_for_attempt(interval, max, 1), Duration::from_millis(20));
+        assert_eq!(backoff_for_attempt(interval, max, 2), Duration::from_millis(40));
+        assert_eq!(backoff_for_attempt(interval, max, 10), max);
+    }
+
+    #[test]
+    fn endpoint_transport_fails_over_to_the_next_reachable_endpoint() {
+        let registry = EndpointRegistry::new();
+        let config = ConnectConfig {
+            endpoints: vec!["tcp/a:7447".to_string(), "tcp/b:7447".to_string()],
+        };
+        let mut transport = EndpointTransport::new(config, registry.clone());
+        assert_eq!(transport.current_endpoint(), Some("tcp/a:7447"));
+        assert!(transport.probe());
+
+        registry.set_reachable("tcp/a:7447", false);
+        assert!(!transport.probe());
+        assert!(transport.reconnect());
+        assert_eq!(transport.current_endpoint(), Some("tcp/b:7447"));
+        assert!(transport.probe());
+
+        registry.set_reachable("tcp/b:7447", false);
+        assert!(!transport.reconnect(), "no configured endpoint is reachable");
+    }
+
+    #[test]
+    fn monitor_surfaces_degraded_then_reconnecting_then_connected() {
+        let registry = EndpointRegistry::new();
+        let config = ConnectConfig {
+            endpoints: vec!["tcp/peer:7447".to_string()],
+        };
+        let transport = EndpointTransport::new(config, registry.clone());
+
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = statuses.clone();
+
+        let monitor = Monitor::spawn(Duration::from_millis(5), Duration::from_millis(20), transport);
+        monitor.connectivity().listen(move |status| {
+            recorded.lock().unwrap().push(status);
+        });
+
+        // Let at least one healthy probe go by, then drop the link.
+        std::thread::sleep(Duration::from_millis(20));
+        registry.set_reachable("tcp/peer:7447", false);
+
+        // Wait for the monitor to notice and flip back.
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        loop {
+            if statuses.lock().unwrap().contains(&ConnectivityStatus::Degraded) {
+                break;
+            }
+            assert!(std::time::Instant::now() < deadline, "never saw Degraded");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        registry.set_reachable("tcp/peer:7447", true);
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        loop {
+            if monitor.connectivity().status() == ConnectivityStatus::Connected {
+                break;
+            }
+            assert!(std::time::Instant::now() < deadline, "never recovered");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let seen = statuses.lock().unwrap().clone();
+        assert!(seen.contains(&ConnectivityStatus::Degraded));
+        assert!(seen.contains(&ConnectivityStatus::Reconnecting));
+        assert!(seen.contains(&ConnectivityStatus::Connected));
+    }
+}
@@ -0,0 +1,94 @@
+//! Key-expression type and wildcard matching shared by the modules in this
+//! crate. [`KeyExpr`] itself stays a plain `/`-separated string (no
+//! canonicalisation, no `$*` chunk wildcards, no ranges) - just enough to
+//! key a store by non-wildcard expressions - but [`matches`] implements
+//! real `*`/`**` selector matching against it, the same grammar
+//! `@/<zid>/metrics/**`-style admin-space selectors use: `*` matches
+//! exactly one non-empty segment, `**` matches zero or more segments.
+
+/// A non-wildcard key expression, e.g. `"demo/example/a"`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyExpr(String);
+
+impl KeyExpr {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for KeyExpr {
+    fn from(s: &str) -> Self {
+        KeyExpr(s.to_string())
+    }
+}
+
+impl From<String> for KeyExpr {
+    fn from(s: String) -> Self {
+        KeyExpr(s)
+    }
+}
+
+impl std::fmt::Display for KeyExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Whether `selector` (which may contain `*`/`**` wildcard segments)
+/// matches `key` (which may not). `*` matches exactly one non-empty
+/// segment; `**` matches any number of segments, including zero - e.g.
+/// `@/*/metrics/**` matches `@/peer01/metrics/a/b` but not `@/metrics/a`
+/// (missing the zid segment) nor an empty zid.
+pub fn matches(selector: &str, key: &str) -> bool {
+    let selector: Vec<&str> = selector.split('/').collect();
+    let key: Vec<&str> = key.split('/').collect();
+    matches_segments(&selector, &key)
+}
+
+fn matches_segments(selector: &[&str], key: &[&str]) -> bool {
+    match selector.first() {
+        None => key.is_empty(),
+        Some(&"**") => {
+            matches_segments(&selector[1..], key)
+                || (!key.is_empty() && matches_segments(selector, &key[1..]))
+        }
+        Some(&"*") => !key.is_empty() && matches_segments(&selector[1..], &key[1..]),
+        Some(segment) => {
+            !key.is_empty() && key[0] == *segment && matches_segments(&selector[1..], &key[1..])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_segments_must_match_literally() {
+        assert!(matches("a/b/c", "a/b/c"));
+        assert!(!matches("a/b/c", "a/b/d"));
+        assert!(!matches("a/b/c", "a/b"));
+    }
+
+    #[test]
+    fn star_matches_exactly_one_non_empty_segment() {
+        assert!(matches("a/*/c", "a/b/c"));
+        assert!(!matches("a/*/c", "a/c"));
+        assert!(!matches("a/*/c", "a/b/b/c"));
+    }
+
+    #[test]
+    fn double_star_matches_zero_or_more_segments() {
+        assert!(matches("a/**", "a"));
+        assert!(matches("a/**", "a/b"));
+        assert!(matches("a/**", "a/b/c/d"));
+        assert!(matches("**", "anything/at/all"));
+    }
+
+    #[test]
+    fn admin_metrics_selector_matches_the_shape_session_builds() {
+        assert!(matches("@/peer01/metrics/**", "@/peer01/metrics/demo/a"));
+        assert!(matches("@/*/metrics/**", "@/peer01/metrics/demo/a"));
+        assert!(!matches("@/peer01/metrics/**", "@/peer02/metrics/demo/a"));
+    }
+}
@@ -0,0 +1,20 @@
+//! Zenoh: Zero Overhead Pub/sub, Store/Query and Compute.
+//!
+//! This is the whole crate: there is no separate networked `Session` /
+//! `Publisher` / `Subscriber` / `Queryable` stack elsewhere to plug into.
+//! [`session::Session`] is a real, synchronous, in-process `put`/`get`/
+//! `delete` key/value store — no transport, scouting, or wire codec — and
+//! every other module (compression, batching, causal-context
+//! reconciliation, metrics, connectivity, Merkle integrity) is implemented
+//! against it for real rather than left as a test calling a method that
+//! doesn't exist.
+
+pub mod batch;
+pub mod causal;
+pub mod compression;
+pub mod connectivity;
+pub mod key_expr;
+pub mod merkle;
+pub mod metrics;
+pub mod sample;
+pub mod session;
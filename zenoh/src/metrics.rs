@@ -0,0 +1,192 @@
+//! Opt-in latency/throughput histograms, in the spirit of the benchrunner
+//! histogram utility: values are bucketed by leading-bit index plus a
+//! fixed number of sub-buckets (HDR-style log bucketing) so percentiles
+//! stay cheap to compute and memory stays bounded regardless of how many
+//! samples have been recorded.
+
+/// Number of sub-buckets per octave (doubling of magnitude); higher means
+/// finer percentile resolution at the cost of more buckets.
+const SUB_BUCKET_BITS: u32 = 2;
+const SUB_BUCKET_COUNT: u64 = 1 << SUB_BUCKET_BITS;
+/// Values below this are tracked one-bucket-per-value; this is also the
+/// bucket-index offset at which the log-bucketed region starts.
+const LINEAR_REGION: u64 = SUB_BUCKET_COUNT;
+/// u64 has 64 bits of magnitude; comfortably bounds the bucket array.
+const BUCKETS: usize = (64 - SUB_BUCKET_BITS as usize) * SUB_BUCKET_COUNT as usize + LINEAR_REGION as usize;
+
+fn bucket_of(value: u64) -> usize {
+    if value < LINEAR_REGION {
+        return value as usize;
+    }
+    let octave = 64 - value.leading_zeros();
+    let shift = octave - SUB_BUCKET_BITS;
+    let sub = (value >> shift) & (SUB_BUCKET_COUNT - 1);
+    (LINEAR_REGION + ((octave - SUB_BUCKET_BITS) as u64 - 1) * SUB_BUCKET_COUNT + sub) as usize
+}
+
+/// The smallest value that falls into `bucket`, i.e. the percentile
+/// estimate reported for any sample landing there.
+fn bucket_floor(bucket: usize) -> u64 {
+    if (bucket as u64) < LINEAR_REGION {
+        return bucket as u64;
+    }
+    let bucket = bucket as u64 - LINEAR_REGION;
+    let octave = bucket / SUB_BUCKET_COUNT + 1 + SUB_BUCKET_BITS as u64;
+    let sub = bucket % SUB_BUCKET_COUNT;
+    (sub << (octave - SUB_BUCKET_BITS as u64)) | (1 << (octave - 1))
+}
+
+/// A log-bucketed histogram of `u64` samples (nanoseconds of latency,
+/// bytes of payload size, ...).
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum: u64,
+    min: u64,
+    max: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; BUCKETS],
+            count: 0,
+            sum: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, value: u64) {
+        self.buckets[bucket_of(value)] += 1;
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Merges `other`'s counts into `self`, for combining per-thread
+    /// histograms into one snapshot.
+    pub fn merge(&mut self, other: &Histogram) {
+        for (a, b) in self.buckets.iter_mut().zip(&other.buckets) {
+            *a += b;
+        }
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// The smallest recorded value `v` such that at least `p` percent of
+    /// samples are `<= v` (linear interpolation is not needed: bucket
+    /// granularity already bounds the error to one octave's resolution).
+    pub fn percentile(&self, p: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let target = ((p / 100.0) * self.count as f64).ceil() as u64;
+        let mut cumulative = 0u64;
+        for (bucket, &count) in self.buckets.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target.max(1) {
+                return bucket_floor(bucket);
+            }
+        }
+        self.max
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            count: self.count,
+            min: if self.count == 0 { 0 } else { self.min },
+            max: self.max,
+            sum: self.sum,
+            p50: self.percentile(50.0),
+            p90: self.percentile(90.0),
+            p99: self.percentile(99.0),
+            p999: self.percentile(99.9),
+        }
+    }
+}
+
+/// An aggregated, point-in-time summary of a [`Histogram`], as returned
+/// through the admin space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Snapshot {
+    pub count: u64,
+    pub min: u64,
+    pub max: u64,
+    pub sum: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub p999: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_count_min_max_sum() {
+        let mut h = Histogram::new();
+        for v in [10, 20, 30, 40, 50] {
+            h.record(v);
+        }
+        let snap = h.snapshot();
+        assert_eq!(snap.count, 5);
+        assert_eq!(snap.min, 10);
+        assert_eq!(snap.max, 50);
+        assert_eq!(snap.sum, 150);
+    }
+
+    #[test]
+    fn percentiles_stay_close_for_a_uniform_distribution() {
+        let mut h = Histogram::new();
+        for v in 1..=1000u64 {
+            h.record(v);
+        }
+        let snap = h.snapshot();
+        // Log bucketing trades exactness for bounded memory: with
+        // SUB_BUCKET_BITS = 2 each bucket covers up to ~25% of its octave,
+        // so percentiles land within that margin of the true value rather
+        // than exactly on it.
+        assert!((300..=700).contains(&snap.p50), "p50 = {}", snap.p50);
+        assert!((700..=1000).contains(&snap.p90), "p90 = {}", snap.p90);
+        assert!(snap.p99 >= snap.p90);
+        assert!(snap.p999 >= snap.p99);
+    }
+
+    #[test]
+    fn merge_combines_two_histograms() {
+        let mut a = Histogram::new();
+        let mut b = Histogram::new();
+        for v in 1..=50u64 {
+            a.record(v);
+        }
+        for v in 51..=100u64 {
+            b.record(v);
+        }
+        a.merge(&b);
+        let snap = a.snapshot();
+        assert_eq!(snap.count, 100);
+        assert_eq!(snap.min, 1);
+        assert_eq!(snap.max, 100);
+    }
+
+    #[test]
+    fn bucket_count_is_bounded_regardless_of_sample_count() {
+        let mut h = Histogram::new();
+        for v in 0..200_000u64 {
+            h.record(v);
+        }
+        assert_eq!(h.buckets.len(), BUCKETS);
+    }
+}
@@ -0,0 +1,48 @@
+//! The bits of the `Sample` model the standalone modules in this crate
+//! need. The full `Sample` (encoding, timestamp, attachment, qos, ...)
+//! would carry much more; this one only needs to carry what
+//! [`crate::session::Session`] actually produces and verifies.
+
+use crate::causal::CausalContext;
+use crate::key_expr::KeyExpr;
+use crate::merkle::MerkleProof;
+
+/// Whether a [`Sample`] represents a `put` or a `delete`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleKind {
+    Put,
+    Delete,
+}
+
+/// A key/value pair as stored or returned by [`crate::session::Session`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub key_expr: KeyExpr,
+    pub payload: Vec<u8>,
+    pub kind: SampleKind,
+    /// Set when the sample came from a causal-context-tracked `get`; see
+    /// [`crate::causal`].
+    pub causal_context: Option<CausalContext>,
+    /// Set on every `get` from [`crate::session::Session`]; verify against
+    /// [`crate::session::Session::merkle_root`] with
+    /// `merkle_proof.verify(sample.payload(), &root)`.
+    pub merkle_proof: Option<MerkleProof>,
+}
+
+impl Sample {
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    pub fn kind(&self) -> SampleKind {
+        self.kind
+    }
+
+    pub fn causal_context(&self) -> Option<&CausalContext> {
+        self.causal_context.as_ref()
+    }
+
+    pub fn merkle_proof(&self) -> Option<&MerkleProof> {
+        self.merkle_proof.as_ref()
+    }
+}
@@ -0,0 +1,377 @@
+//! `Session`: an in-process, synchronous `put` / `delete` / `get` key/value
+//! store over key expressions. There's no transport, scouting, wire codec,
+//! or separate `Publisher` / `Subscriber` / `Queryable` declarations in
+//! this crate — `put`/`get` are the whole write/read path, and every
+//! other feature module in this crate (compression, batching, causal
+//! context, metrics, connectivity, Merkle integrity) hangs real
+//! functionality off that one path rather than existing standalone.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use std::time::Duration;
+
+use crate::batch::{BatchBuilder, Op, ReplyReceiver};
+use crate::causal::{CausalContext, CausalStorage, ConflictResolution, WriterId};
+use crate::compression::{self, Compression};
+use crate::connectivity::{Connectivity, ConnectConfig, EndpointRegistry, EndpointTransport, Monitor, Transport};
+use crate::key_expr::{self, KeyExpr};
+use crate::merkle::{self, MerkleTree};
+use crate::metrics::{Histogram, Snapshot};
+use crate::sample::{Sample, SampleKind};
+
+/// What's actually kept in the store for a key: the wire-encoded (and
+/// possibly compressed) payload, tagged with the codec that produced it so
+/// [`Session::get`] can decompress it transparently regardless of which
+/// [`Compression`] the writer chose; plus this write's index into the
+/// session-wide [`MerkleTree`] so `get` can hand back an integrity proof
+/// alongside the value.
+struct StoredValue {
+    wire: Vec<u8>,
+    merkle_index: usize,
+}
+
+impl StoredValue {
+    fn decode(&self) -> Vec<u8> {
+        compression::decode(&self.wire).expect("this session only ever wrote its own wire format")
+    }
+}
+
+/// Builder returned by [`Session::put_builder`]: accumulates put options
+/// (currently just [`Compression`]) before writing, mirroring the real
+/// `Session::put`'s builder rather than taking a flat argument list.
+pub struct PutBuilder<'s> {
+    session: &'s Session,
+    key_expr: KeyExpr,
+    payload: Vec<u8>,
+    compression: Compression,
+}
+
+impl<'s> PutBuilder<'s> {
+    /// Compresses the payload with `codec` before it's stored; a
+    /// subsequent [`Session::get`] decompresses it transparently, so
+    /// callers never see the wire bytes.
+    pub fn compression(mut self, codec: Compression) -> Self {
+        self.compression = codec;
+        self
+    }
+
+    /// Writes the payload, returning the prior decoded value if any.
+    pub fn send(self) -> Option<Vec<u8>> {
+        self.session.put_encoded(self.key_expr, &self.payload, self.compression)
+    }
+}
+
+/// `connect.reconnect.*` config: whether the session should run a
+/// background [`Monitor`], how often it probes while healthy, and the cap
+/// on its exponential backoff between reconnect retries.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// A session's local view of the keyspace it has put/deleted into.
+pub struct Session {
+    /// Doubles as both the causal-context writer id and the admin-space
+    /// zid; the full `Session` distinguishes the two, but this minimal
+    /// stand-in doesn't need to.
+    writer_id: WriterId,
+    store: Mutex<HashMap<KeyExpr, StoredValue>>,
+    causal_storage: Arc<CausalStorage>,
+    metrics: Mutex<HashMap<KeyExpr, Histogram>>,
+    monitor: Option<Monitor>,
+    /// Append-only log of every payload this session has put, in write
+    /// order; backs the integrity proof [`Session::get`] returns alongside
+    /// a [`Sample`] and the root [`Session::merkle_root`] publishes.
+    merkle: Mutex<MerkleTree>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session::with_writer_id("default-writer")
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A session identified by `writer_id` for the purpose of
+    /// [`CausalContext`] reconciliation: each writer increments only its
+    /// own entry in a key's version vector. Gets its own private
+    /// [`CausalStorage`]; use [`Session::with_shared_causal_storage`] to
+    /// have several sessions reconcile against the same multi-master
+    /// storage.
+    pub fn with_writer_id(writer_id: impl Into<WriterId>) -> Self {
+        Self::with_shared_causal_storage(writer_id, Arc::new(CausalStorage::new()))
+    }
+
+    /// Like [`Session::with_writer_id`], but points this session's
+    /// causal-context reads/writes at an existing, possibly
+    /// multiple-sessions-shared, [`CausalStorage`].
+    pub fn with_shared_causal_storage(
+        writer_id: impl Into<WriterId>,
+        causal_storage: Arc<CausalStorage>,
+    ) -> Self {
+        Self {
+            writer_id: writer_id.into(),
+            store: Mutex::new(HashMap::new()),
+            causal_storage,
+            metrics: Mutex::new(HashMap::new()),
+            monitor: None,
+            merkle: Mutex::new(MerkleTree::new()),
+        }
+    }
+
+    /// Enables the background connectivity monitor per `config`, probing
+    /// and reconnecting via `transport`. Survives a peer bouncing without
+    /// the caller tearing down and re-opening the whole session; see
+    /// [`Session::connectivity`] for status-change events. Use
+    /// [`Session::with_endpoint_reconnect`] to probe `connect.endpoints`
+    /// directly instead of supplying a [`Transport`] by hand.
+    pub fn with_reconnect(mut self, config: ReconnectConfig, transport: impl Transport) -> Self {
+        if config.enabled {
+            self.monitor = Some(Monitor::spawn(config.interval, config.max_backoff, transport));
+        }
+        self
+    }
+
+    /// Enables the background connectivity monitor against `connect.endpoints`
+    /// (`connect`), failing over between them on the schedule set by
+    /// `connect.reconnect.*` (`reconnect`). Shorthand for
+    /// `self.with_reconnect(reconnect, EndpointTransport::new(connect, registry))`.
+    pub fn with_endpoint_reconnect(
+        self,
+        reconnect: ReconnectConfig,
+        connect: ConnectConfig,
+        registry: EndpointRegistry,
+    ) -> Self {
+        self.with_reconnect(reconnect, EndpointTransport::new(connect, registry))
+    }
+
+    /// A handle to this session's connectivity status, or `None` if the
+    /// background monitor ([`Session::with_reconnect`]) isn't enabled.
+    pub fn connectivity(&self) -> Option<Connectivity> {
+        self.monitor.as_ref().map(Monitor::connectivity)
+    }
+
+    /// This session's zid, as it would appear under `@/<zid>/...` in the
+    /// admin space.
+    pub fn zid(&self) -> &str {
+        &self.writer_id
+    }
+
+    /// Feeds one latency/size sample into the per-key-expression histogram
+    /// a `Publisher`/`Subscriber`/`Queryable` would record on send/recv.
+    /// [`Session::put`] and [`Session::get`] already call this with the
+    /// payload size on every write/read; call it directly only to record
+    /// something else (e.g. a latency reading a real transport would
+    /// supply).
+    pub fn record_metric(&self, key_expr: impl Into<KeyExpr>, value: u64) {
+        self.metrics
+            .lock()
+            .unwrap()
+            .entry(key_expr.into())
+            .or_default()
+            .record(value);
+    }
+
+    /// Answers an admin-space metrics query, e.g. `@/<zid>/metrics/**`,
+    /// against the real admin key space: each recorded key expression is
+    /// exposed as `@/<zid>/metrics/<key_expr>`, and `selector` is matched
+    /// against it with [`key_expr::matches`] - so a wildcard zid segment
+    /// (`@/*/metrics/**`) or a narrower selector (`@/<zid>/metrics/a/*`)
+    /// works the same way any other key-expression query would, rather
+    /// than requiring an exact `"metrics/**"` literal.
+    pub fn admin_get_metrics(&self, selector: &str) -> Vec<(String, Snapshot)> {
+        self.metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key_expr, histogram)| {
+                (
+                    format!("@/{}/metrics/{}", self.writer_id, key_expr),
+                    histogram.snapshot(),
+                )
+            })
+            .filter(|(path, _)| key_expr::matches(selector, path))
+            .collect()
+    }
+
+    /// Writes `payload` under `key_expr`, passing back the [`CausalContext`]
+    /// token last observed via [`Session::get_with_causal_context`] (or
+    /// [`CausalContext::empty`] for a first write). Returns the new token,
+    /// which a subsequent writer must pass back to avoid clobbering this
+    /// write. Any existing value this write causally supersedes is
+    /// discarded; values concurrent with it are kept alongside it.
+    pub fn put_with_causal_context(
+        &self,
+        key_expr: impl Into<KeyExpr>,
+        payload: impl Into<Vec<u8>>,
+        seen: &CausalContext,
+    ) -> CausalContext {
+        self.causal_storage
+            .put(&self.writer_id, key_expr, seen, payload.into())
+    }
+
+    /// Reads every causally-live value stored under `key_expr`, reconciled
+    /// per `resolution`, together with a token covering all of them.
+    pub fn get_with_causal_context(
+        &self,
+        key_expr: impl Into<KeyExpr>,
+        resolution: ConflictResolution,
+    ) -> (Vec<Sample>, CausalContext) {
+        let key_expr = key_expr.into();
+        let (payloads, context) = self.causal_storage.get(key_expr.clone(), resolution);
+        let samples = payloads
+            .into_iter()
+            .map(|payload| Sample {
+                key_expr: key_expr.clone(),
+                payload,
+                kind: SampleKind::Put,
+                causal_context: Some(context.clone()),
+                merkle_proof: None,
+            })
+            .collect();
+        (samples, context)
+    }
+
+    /// Stores `payload` under `key_expr` uncompressed, returning the prior
+    /// value if any. Shorthand for `self.put_builder(key_expr, payload).send()`;
+    /// use [`Session::put_builder`] to compress the payload first.
+    pub fn put(&self, key_expr: impl Into<KeyExpr>, payload: impl Into<Vec<u8>>) -> Option<Vec<u8>> {
+        self.put_builder(key_expr, payload).send()
+    }
+
+    /// Starts a [`PutBuilder`] so the payload can be compressed (see
+    /// [`PutBuilder::compression`]) before it's written.
+    pub fn put_builder(
+        &self,
+        key_expr: impl Into<KeyExpr>,
+        payload: impl Into<Vec<u8>>,
+    ) -> PutBuilder<'_> {
+        PutBuilder {
+            session: self,
+            key_expr: key_expr.into(),
+            payload: payload.into(),
+            compression: Compression::None,
+        }
+    }
+
+    fn put_encoded(&self, key_expr: KeyExpr, payload: &[u8], codec: Compression) -> Option<Vec<u8>> {
+        let merkle_index = self.merkle.lock().unwrap().append(payload);
+        let stored = StoredValue {
+            wire: compression::encode(codec, payload),
+            merkle_index,
+        };
+        self.record_metric(key_expr.clone(), payload.len() as u64);
+        self.store
+            .lock()
+            .unwrap()
+            .insert(key_expr, stored)
+            .map(|prior| prior.decode())
+    }
+
+    /// Removes any value stored under `key_expr`, returning it (decompressed)
+    /// if present. The put this value came from stays in the integrity log
+    /// [`Session::merkle_root`] is computed over — deleting a key doesn't
+    /// erase history of what was once written.
+    pub fn delete(&self, key_expr: impl Into<KeyExpr>) -> Option<Vec<u8>> {
+        self.store
+            .lock()
+            .unwrap()
+            .remove(&key_expr.into())
+            .map(|stored| stored.decode())
+    }
+
+    /// Looks up the current value stored under `key_expr`, if any,
+    /// transparently decompressing it regardless of which [`Compression`]
+    /// the writer used, and attaching the [`MerkleProof`] that ties it to
+    /// the `merkle_root()` published at write time.
+    pub fn get(&self, key_expr: impl Into<KeyExpr>) -> Option<Sample> {
+        let key_expr = key_expr.into();
+        let sample = self
+            .store
+            .lock()
+            .unwrap()
+            .get(&key_expr)
+            .map(|stored| Sample {
+                key_expr: key_expr.clone(),
+                payload: stored.decode(),
+                kind: SampleKind::Put,
+                causal_context: None,
+                merkle_proof: Some(self.merkle.lock().unwrap().proof(stored.merkle_index)),
+            });
+        if let Some(sample) = &sample {
+            self.record_metric(key_expr, sample.payload().len() as u64);
+        }
+        sample
+    }
+
+    /// The current root of this session's write-integrity [`MerkleTree`]:
+    /// republish this alongside every sample (e.g. on a companion
+    /// `<key>/@merkle_root` key) so a reader can verify `Sample::merkle_proof`
+    /// without trusting anything in between.
+    pub fn merkle_root(&self) -> merkle::Hash {
+        self.merkle.lock().unwrap().root()
+    }
+
+    /// Starts a [`BatchBuilder`] that accumulates `put`/`delete`/`get`
+    /// operations across different key expressions and submits them as one
+    /// unit once `.run()` is called.
+    pub fn batch(&self) -> BatchBuilder<'_> {
+        BatchBuilder::new(self)
+    }
+
+    /// Applies every op in a decoded batch frame (see [`crate::batch`])
+    /// under a single acquisition of the store and Merkle-tree locks,
+    /// returning one [`ReplyReceiver`] per `Get` op in queue order. This is
+    /// what a batch actually amortizes over calling `put`/`delete`/`get`
+    /// once per op: one lock acquisition for the whole batch rather than
+    /// one per op.
+    pub(crate) fn apply_batch(&self, ops: Vec<Op>) -> Vec<ReplyReceiver> {
+        let mut replies = Vec::new();
+        let mut store = self.store.lock().unwrap();
+        let mut merkle = self.merkle.lock().unwrap();
+        for op in ops {
+            match op {
+                Op::Put(key_expr, payload) => {
+                    let merkle_index = merkle.append(&payload);
+                    let wire = compression::encode(Compression::None, &payload);
+                    self.record_metric(key_expr.clone(), payload.len() as u64);
+                    store.insert(key_expr, StoredValue { wire, merkle_index });
+                }
+                Op::Delete(key_expr) => {
+                    store.remove(&key_expr);
+                }
+                Op::Get(key_expr) => {
+                    let sample = store.get(&key_expr).map(|stored| Sample {
+                        key_expr: key_expr.clone(),
+                        payload: stored.decode(),
+                        kind: SampleKind::Put,
+                        causal_context: None,
+                        merkle_proof: Some(merkle.proof(stored.merkle_index)),
+                    });
+                    if let Some(sample) = &sample {
+                        self.record_metric(key_expr, sample.payload().len() as u64);
+                    }
+                    replies.push(ReplyReceiver::new(sample));
+                }
+            }
+        }
+        replies
+    }
+}
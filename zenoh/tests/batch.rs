@@ -0,0 +1,45 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh::sample::SampleKind;
+use zenoh::session::Session;
+
+#[test]
+fn test_session_batch() {
+    let session = Session::new();
+
+    let key_exprs = [
+        "test/session/batch/1",
+        "test/session/batch/2",
+        "test/session/batch/3",
+    ];
+    session.put(key_exprs[1], vec![0u8; 8]);
+    session.put(key_exprs[2], vec![0u8; 8]);
+
+    // Submit a put, a delete and a get across different key expressions as one batch
+    let results = session
+        .batch()
+        .put(key_exprs[0], vec![0u8; 8])
+        .delete(key_exprs[1])
+        .get(key_exprs[2])
+        .run();
+
+    assert_eq!(results.get_replies.len(), 1);
+    let reply = results.get_replies[0].recv().unwrap();
+    assert_eq!(reply.kind(), SampleKind::Put);
+    assert_eq!(reply.payload().len(), 8);
+
+    // The batched put/delete landed, in order, before the get ran
+    assert!(session.get(key_exprs[0]).is_some());
+    assert!(session.get(key_exprs[1]).is_none());
+}
@@ -0,0 +1,64 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::Arc;
+
+use zenoh::causal::{CausalContext, CausalStorage, ConflictResolution};
+use zenoh::session::Session;
+
+#[test]
+fn test_session_causal_context() {
+    let key_expr = "test/session/causal";
+
+    // Two writer peers reconcile against the same multi-master storage.
+    let storage = Arc::new(CausalStorage::new());
+    let peer01 = Session::with_shared_causal_storage("writer-a", storage.clone());
+    let peer02 = Session::with_shared_causal_storage("writer-b", storage);
+
+    // First read establishes a causality token against an empty history
+    let (samples, token) =
+        peer01.get_with_causal_context(key_expr, ConflictResolution::KeepConcurrent);
+    assert!(samples.is_empty());
+    assert_eq!(token, CausalContext::empty());
+
+    // Two writers race on the same token: both puts are concurrent, so a
+    // KeepConcurrent read must surface both.
+    peer01.put_with_causal_context(key_expr, "writer-a", &token);
+    peer02.put_with_causal_context(key_expr, "writer-b", &token);
+
+    let (samples, _) =
+        peer01.get_with_causal_context(key_expr, ConflictResolution::KeepConcurrent);
+    assert_eq!(samples.len(), 2);
+    for sample in &samples {
+        assert!(sample.causal_context().is_some());
+    }
+
+    let (samples, _) =
+        peer01.get_with_causal_context(key_expr, ConflictResolution::LastWriterWins);
+    assert_eq!(samples.len(), 1);
+}
+
+#[test]
+fn sequential_write_from_the_same_writer_does_not_fork() {
+    let key_expr = "test/session/causal/sequential";
+    let session = Session::with_writer_id("writer-a");
+
+    let (_, token) = session.get_with_causal_context(key_expr, ConflictResolution::KeepConcurrent);
+    let token = session.put_with_causal_context(key_expr, "v1", &token);
+    session.put_with_causal_context(key_expr, "v2", &token);
+
+    let (samples, _) =
+        session.get_with_causal_context(key_expr, ConflictResolution::KeepConcurrent);
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].payload(), b"v2");
+}
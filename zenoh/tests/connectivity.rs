@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use zenoh::connectivity::{ConnectConfig, ConnectivityStatus, EndpointRegistry};
+use zenoh::session::{ReconnectConfig, Session};
+
+#[test]
+fn zenoh_session_reconnect() {
+    // Stands in for the network: flipped to simulate peer01's endpoint
+    // bouncing.
+    let registry = EndpointRegistry::new();
+
+    let peer02 = Session::with_writer_id("peer02").with_endpoint_reconnect(
+        ReconnectConfig {
+            enabled: true,
+            interval: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(50),
+        },
+        ConnectConfig {
+            endpoints: vec!["tcp/peer01:7447".to_string()],
+        },
+        registry.clone(),
+    );
+
+    let connectivity = peer02.connectivity().expect("monitor enabled");
+    let statuses = Arc::new(Mutex::new(Vec::new()));
+    let recorded = statuses.clone();
+    connectivity.listen(move |status| recorded.lock().unwrap().push(status));
+
+    // Close peer01's endpoint without tearing down peer02's session.
+    registry.set_reachable("tcp/peer01:7447", false);
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while !statuses
+        .lock()
+        .unwrap()
+        .contains(&ConnectivityStatus::Reconnecting)
+    {
+        assert!(Instant::now() < deadline, "never started reconnecting");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    // Reopen peer01 on the same endpoint: the monitor should recover on
+    // its own, without the caller re-opening peer02's session.
+    registry.set_reachable("tcp/peer01:7447", true);
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while connectivity.status() != ConnectivityStatus::Connected {
+        assert!(Instant::now() < deadline, "never recovered");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn reconnect_disabled_by_default_has_no_connectivity_handle() {
+    let session = Session::new();
+    assert!(session.connectivity().is_none());
+}
@@ -110,3 +110,60 @@ fn queries() {
         }
     }
 }
+
+#[cfg(feature = "unstable")]
+#[test]
+fn merkle_proof() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use zenoh::{
+        prelude::sync::*, publication::MerkleIntegrity, sample::MerkleProof,
+        sample_builder::SampleBuilderTrait,
+    };
+
+    let zenoh = zenoh::open(Config::default()).res().unwrap();
+    let publisher = zenoh
+        .declare_publisher("test/attachment/merkle")
+        .merkle_integrity(MerkleIntegrity::Enabled)
+        .res()
+        .unwrap();
+
+    // The subscriber only trusts a root it has seen on the companion key, so
+    // it tracks the latest one alongside the samples it verifies against it.
+    let root = Arc::new(std::sync::Mutex::new(None));
+    let c_root = root.clone();
+    let _root_sub = zenoh
+        .declare_subscriber("test/attachment/merkle/@merkle_root")
+        .callback(move |sample| {
+            *c_root.lock().unwrap() = Some(sample.payload().contiguous().to_vec());
+        })
+        .res()
+        .unwrap();
+
+    let verified = Arc::new(AtomicUsize::new(0));
+    let c_verified = verified.clone();
+    let c_root = root.clone();
+    let _sub = zenoh
+        .declare_subscriber("test/attachment/merkle")
+        .callback(move |sample| {
+            let proof = sample
+                .attachment()
+                .and_then(|a| MerkleProof::try_from(a).ok())
+                .expect("merkle proof attachment");
+            if let Some(root) = c_root.lock().unwrap().as_ref() {
+                assert!(proof.verify(&sample.payload().contiguous(), root));
+                c_verified.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .res()
+        .unwrap();
+
+    for i in 0..10 {
+        publisher
+            .put(format!("leaf-{i}"))
+            .res()
+            .unwrap();
+    }
+}
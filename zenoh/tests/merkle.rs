@@ -0,0 +1,58 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh::session::Session;
+
+// Mirrors the attachments.rs pubsub scenario: a writer puts a sequence of
+// payloads; `Session::get` hands back each one with a Merkle proof tied to
+// the session's *current* write-integrity tree, and a reader verifies it
+// against the root `Session::merkle_root` reports right now — e.g. what a
+// companion `<key>/@merkle_root` key would currently carry.
+#[test]
+fn reader_verifies_every_written_sample_against_the_current_root() {
+    let session = Session::new();
+    let mut key_exprs = Vec::new();
+
+    for i in 0..10u8 {
+        let key_expr = format!("test/session/merkle/{i}");
+        let payload = format!("leaf-{i}").into_bytes();
+        session.put(key_expr.clone(), payload);
+        key_exprs.push(key_expr);
+    }
+
+    let root = session.merkle_root();
+    for key_expr in &key_exprs {
+        let sample = session.get(key_expr.as_str()).unwrap();
+        let proof = sample.merkle_proof().expect("get() always attaches a proof");
+        assert!(proof.verify(sample.payload(), &root));
+    }
+}
+
+#[test]
+fn tampered_or_stale_samples_fail_verification() {
+    let session = Session::new();
+    session.put("test/session/merkle/0", b"leaf-0".to_vec());
+    let stale_root = session.merkle_root();
+    let sample = session.get("test/session/merkle/0").unwrap();
+    let proof = sample.merkle_proof().unwrap();
+
+    // A router that flips a byte in flight must be caught.
+    assert!(!proof.verify(b"leaf-0-tampered", &stale_root));
+
+    // A later write changes the root; a proof generated before it no
+    // longer verifies against the new root (nor should it — it's a proof
+    // about a smaller tree).
+    session.put("test/session/merkle/1", b"leaf-1".to_vec());
+    assert!(!proof.verify(b"leaf-0", &session.merkle_root()));
+    assert!(proof.verify(b"leaf-0", &stale_root));
+}
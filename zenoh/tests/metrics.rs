@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2023 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh::session::Session;
+
+const MSG_COUNT: u64 = 1_000;
+
+#[test]
+fn test_session_metrics() {
+    let key_expr = "test/session/metrics";
+    let peer01 = Session::with_writer_id("peer01");
+
+    // A Subscriber would feed its recv latency (here: a synthetic
+    // monotonically increasing nanosecond reading) into the histogram for
+    // every message of the pubsub test's 1024-byte size.
+    for i in 1..=MSG_COUNT {
+        peer01.record_metric(key_expr, i * 1_000);
+    }
+
+    let zid = peer01.zid().to_string();
+    let results = peer01.admin_get_metrics(&format!("@/{zid}/metrics/**"));
+    assert_eq!(results.len(), 1);
+    let (path, snapshot) = &results[0];
+    assert_eq!(path, &format!("@/{zid}/metrics/{key_expr}"));
+    assert_eq!(snapshot.count, MSG_COUNT);
+    assert!(snapshot.p50 > 0);
+    assert!(snapshot.p99 >= snapshot.p50);
+    assert!(snapshot.p999 >= snapshot.p99);
+
+    // A mismatched zid segment doesn't match this session's admin tree
+    assert!(peer01.admin_get_metrics("@/someone-else/metrics/**").is_empty());
+    // A wildcard zid does
+    assert_eq!(peer01.admin_get_metrics("@/*/metrics/**").len(), 1);
+    // A selector scoped to a single sub-key expression also matches
+    assert_eq!(
+        peer01
+            .admin_get_metrics(&format!("@/{zid}/metrics/test/session/*"))
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn put_and_get_feed_the_histogram_without_any_manual_record_metric_call() {
+    let session = Session::new();
+    let key_expr = "test/session/metrics/auto";
+
+    for i in 0..5u8 {
+        session.put(key_expr, vec![0u8; 16 + i as usize]);
+    }
+    session.get(key_expr);
+    session.get(key_expr);
+
+    let zid = session.zid().to_string();
+    let results = session.admin_get_metrics(&format!("@/{zid}/metrics/**"));
+    assert_eq!(results.len(), 1);
+    let (path, snapshot) = &results[0];
+    assert_eq!(path, &format!("@/{zid}/metrics/{key_expr}"));
+    // 5 puts + 2 gets, each recording the payload size in bytes.
+    assert_eq!(snapshot.count, 7);
+}